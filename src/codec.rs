@@ -0,0 +1,153 @@
+/*
+ *     Copyright 2025 The Dragonfly Authors
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::Error;
+use crate::{Vortex, CHECKSUM_FLAG, CRC_SIZE, HEADER_SIZE};
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+
+/// DEFAULT_MAX_VALUE_LEN is the default ceiling on a packet's declared value length, matching the
+/// protocol's documented 1 GiB value size limit.
+pub const DEFAULT_MAX_VALUE_LEN: u32 = 1 << 30;
+
+/// VortexCodec frames Vortex packets off an async byte stream, via
+/// [`tokio_util::codec::Framed`]. Unlike `Vortex::from_bytes`, it never requires the whole value
+/// to already be buffered in memory: it waits for exactly `length` bytes to arrive before
+/// emitting a packet, which is what makes it workable for `PieceContent` values approaching the
+/// protocol's 1 GiB limit.
+#[derive(Debug)]
+pub struct VortexCodec {
+    max_value_len: u32,
+}
+
+/// VortexCodec implements the VortexCodec functions.
+impl VortexCodec {
+    /// new creates a VortexCodec that rejects packets declaring a value longer than
+    /// `DEFAULT_MAX_VALUE_LEN`.
+    pub fn new() -> Self {
+        Self::with_max_value_len(DEFAULT_MAX_VALUE_LEN)
+    }
+
+    /// with_max_value_len creates a VortexCodec that rejects packets declaring a value longer
+    /// than `max_value_len`, so a peer can't force an unbounded buffer allocation by lying about
+    /// the length in the header.
+    pub fn with_max_value_len(max_value_len: u32) -> Self {
+        Self { max_value_len }
+    }
+}
+
+impl Default for VortexCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Decoder reads a complete Vortex packet off the wire, buffering incrementally until a whole
+/// frame has arrived.
+impl Decoder for VortexCodec {
+    type Item = Vortex;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.len() < HEADER_SIZE {
+            return Ok(None);
+        }
+
+        let raw_length = u32::from_be_bytes(src[2..HEADER_SIZE].try_into()?);
+        let checksum = raw_length & CHECKSUM_FLAG != 0;
+        let length = raw_length & !CHECKSUM_FLAG;
+        if length > self.max_value_len {
+            return Err(Error::InvalidLength(format!(
+                "declared value length {length} exceeds max_value_len {}",
+                self.max_value_len
+            )));
+        }
+
+        let frame_len = HEADER_SIZE + length as usize + if checksum { CRC_SIZE } else { 0 };
+        if src.len() < frame_len {
+            // Reserve the rest of the frame up front instead of growing the buffer one read at a
+            // time while we wait for it to arrive.
+            src.reserve(frame_len - src.len());
+            return Ok(None);
+        }
+
+        let frame = src.split_to(frame_len).freeze();
+        Vortex::from_bytes(frame).map(Some)
+    }
+}
+
+/// Encoder writes a Vortex packet directly into the buffer the framework supplies.
+impl Encoder<Vortex> for VortexCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Vortex, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        item.write_into(dst);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tlv::Tag;
+    use bytes::Bytes;
+
+    #[test]
+    fn test_decode_waits_for_full_frame() {
+        let packet = Vortex::new(Tag::DownloadPiece, Bytes::from("a".repeat(32) + "-42"))
+            .expect("Failed to create Vortex packet");
+        let encoded = packet.to_bytes();
+
+        let mut codec = VortexCodec::new();
+        let mut src = BytesMut::from(&encoded[..encoded.len() - 1]);
+        assert!(codec.decode(&mut src).unwrap().is_none());
+
+        src.extend_from_slice(&encoded[encoded.len() - 1..]);
+        let decoded = codec.decode(&mut src).unwrap().expect("Expected a packet");
+        assert_eq!(decoded.to_bytes(), encoded);
+    }
+
+    #[test]
+    fn test_decode_rejects_oversized_length() {
+        let mut codec = VortexCodec::with_max_value_len(4);
+        let mut src = BytesMut::new();
+        src.extend_from_slice(&[0u8, Tag::PieceContent.into(), 0, 0, 0, 5]);
+
+        assert!(codec.decode(&mut src).is_err());
+    }
+
+    #[test]
+    fn test_io_error_converts_into_error_io() {
+        // Decoder::Error must implement From<io::Error> so Framed can surface a failure reading
+        // the underlying byte stream through VortexCodec's error type; this exercises that
+        // conversion directly, since VortexCodec itself never does I/O on its own.
+        let io_err = std::io::Error::other("boom");
+        assert!(matches!(Error::from(io_err), Error::Io(_)));
+    }
+
+    #[test]
+    fn test_encode_matches_to_bytes() {
+        let packet = Vortex::new(Tag::DownloadPiece, Bytes::from("a".repeat(32) + "-42"))
+            .expect("Failed to create Vortex packet");
+        let expected = packet.to_bytes();
+
+        let mut codec = VortexCodec::new();
+        let mut dst = BytesMut::new();
+        codec.encode(packet, &mut dst).unwrap();
+
+        assert_eq!(dst.freeze(), expected);
+    }
+}
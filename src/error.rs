@@ -0,0 +1,69 @@
+/*
+ *     Copyright 2025 The Dragonfly Authors
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::array::TryFromSliceError;
+use thiserror::Error as ThisError;
+
+/// Result is a type alias for the standard library result type, using the crate's Error as the
+/// error type.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Error is the error type for the Vortex protocol.
+#[derive(Debug, ThisError)]
+pub enum Error {
+    /// InvalidPacket is returned when a packet is malformed.
+    #[error("invalid packet: {0}")]
+    InvalidPacket(String),
+
+    /// InvalidLength is returned when the declared length does not match the actual value
+    /// length, when it exceeds `VortexCodec`'s `max_value_len`, or when a value's parser leaves
+    /// part of the declared value unconsumed.
+    #[error("invalid length: {0}")]
+    InvalidLength(String),
+
+    /// Io wraps an I/O error encountered while reading or writing the underlying transport. The
+    /// `#[from]` conversion is required by `tokio_util::codec::Decoder`'s `Self::Error:
+    /// From<io::Error>` bound, which `VortexCodec` relies on: `Framed` converts an I/O failure
+    /// from the byte stream it's reading into this variant via that bound.
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Transport is returned when a QUIC connection or stream operation fails, as distinct from a
+    /// malformed packet arriving over an otherwise healthy connection.
+    #[cfg(feature = "transport")]
+    #[error("transport error: {0}")]
+    Transport(String),
+
+    /// Peer is returned when a request receives a well-formed `Tag::Error` response, meaning the
+    /// peer understood the request but refused or failed to answer it — distinct from
+    /// `InvalidPacket`, which means the response itself couldn't be parsed.
+    #[cfg(feature = "transport")]
+    #[error("peer returned error {code}: {message}")]
+    Peer { code: u8, message: String },
+
+    /// ChecksumMismatch is returned when a packet's trailing CRC32C does not match the header and
+    /// value it was computed over, indicating the packet was corrupted in transit.
+    #[error("checksum mismatch: {0}")]
+    ChecksumMismatch(String),
+}
+
+/// Converts a slice conversion error, as produced by decoding the fixed-size length field, into
+/// an Error.
+impl From<TryFromSliceError> for Error {
+    fn from(err: TryFromSliceError) -> Self {
+        Error::InvalidPacket(err.to_string())
+    }
+}
@@ -15,15 +15,29 @@
  */
 
 use crate::error::{Error, Result};
+use crate::tlv::{Serialize, TryParse};
 use bytes::{BufMut, Bytes, BytesMut};
 use rand::prelude::*;
 
+pub mod codec;
 pub mod error;
 pub mod tlv;
+#[cfg(feature = "transport")]
+pub mod transport;
 
 /// HEADER_SIZE is the size of the Vortex packet header including the packet identifier, tag, and
 /// length.
-const HEADER_SIZE: usize = 6;
+pub(crate) const HEADER_SIZE: usize = 6;
+
+/// CRC_SIZE is the size of the trailing CRC32C checksum appended after the value when a packet
+/// has its checksum flag set.
+pub(crate) const CRC_SIZE: usize = 4;
+
+/// CHECKSUM_FLAG is the top bit of the 32-bit length field, repurposed as a flag marking whether a
+/// CRC32C checksum trails the value. Declared lengths never approach `u32::MAX` (the protocol caps
+/// a value at 1 GiB), so this bit is otherwise always zero, and packets that don't set it parse
+/// exactly as they did before checksums existed.
+pub(crate) const CHECKSUM_FLAG: u32 = 1 << 31;
 
 /// Header represents the Vortex packet header.
 #[derive(Debug)]
@@ -31,6 +45,7 @@ pub struct Header {
     packet_id: u8,
     tag: tlv::Tag,
     length: usize,
+    checksum: bool,
 }
 
 /// Vortex Protocol
@@ -41,17 +56,21 @@ pub struct Header {
 /// Packet Format:
 ///     - Packet Identifier (8 bits): Uniquely identifies each packet
 ///     - Tag (8 bits): Specifies data type in value field
-///     - Length (32 bits): Indicates Value field length, up to 4 GiB
+///     - Length (32 bits): Indicates Value field length. The top bit is `CHECKSUM_FLAG`, marking
+///       whether a trailing CRC32C follows the value, which caps the real length at `2^31 - 1`
 ///     - Value (variable): Actual data content, maximum 1 GiB
+///     - Checksum (32 bits, optional): Trailing little-endian CRC32C over the header and value,
+///       present iff `CHECKSUM_FLAG` is set
 ///
 /// Protocol Format:
 ///
 /// ```text
-/// -------------------------------------------------------------------------------------------------
-/// |                            |                   |                    |                         |
-/// | Packet Identifier (8 bits) |    Tag (8 bits)   |  Length (32 bits)  |   Value (up to 4 GiB)   |
-/// |                            |                   |                    |                         |
-/// -------------------------------------------------------------------------------------------------
+/// ----------------------------------------------------------------------------------------------------------------
+/// |                            |                   |                    |                         |             |
+/// | Packet Identifier (8 bits) |    Tag (8 bits)   |  Length (32 bits)  |   Value (up to 1 GiB)   | Checksum      |
+/// |                            |                   |                    |                         | (32 bits,     |
+/// |                            |                   |                    |                         | optional)     |
+/// ----------------------------------------------------------------------------------------------------------------
 /// ```
 ///
 /// For more information, please refer to the [Vortex Protocol](https://github.com/dragonflyoss/vortex/blob/main/docs/README.md).
@@ -59,7 +78,7 @@ pub struct Header {
 pub enum Vortex {
     DownloadPiece(Header, tlv::download_piece::DownloadPiece),
     PieceContent(Header, tlv::piece_content::PieceContent),
-    Reserved(Header),
+    Reserved(Header, Box<dyn tlv::Value>),
     Error(Header, tlv::error::Error),
 }
 
@@ -68,63 +87,108 @@ impl Vortex {
     /// Creates a new Vortex packet.
     pub fn new(tag: tlv::Tag, value: Bytes) -> Result<Self> {
         let mut rng = rand::thread_rng();
+        Self::build(rng.gen(), tag, value, false)
+    }
+
+    /// new_checksummed creates a new Vortex packet whose `to_bytes` output carries a trailing
+    /// CRC32C checksum over the header and value, for end-to-end corruption detection across
+    /// untrusted intermediaries.
+    pub fn new_checksummed(tag: tlv::Tag, value: Bytes) -> Result<Self> {
+        let mut rng = rand::thread_rng();
+        Self::build(rng.gen(), tag, value, true)
+    }
+
+    /// reply builds a response packet carrying the same `packet_id` as `request`, which is how a
+    /// peer correlates a response with the request it answers. The response is checksummed iff
+    /// the request was.
+    pub fn reply(request: &Vortex, tag: tlv::Tag, value: Bytes) -> Result<Self> {
+        Self::build(request.packet_id(), tag, value, request.header().checksum)
+    }
+
+    /// build assembles a Vortex packet by dispatching `value` to the `TryParse` implementation
+    /// registered for `tag`. This is the single place that maps a `Tag` to its TLV value type;
+    /// adding a new tag means extending this match, the `Vortex` enum, and the per-variant matches
+    /// in `header`, `serialized_value_len` and `write_into`. `Tag::Reserved` tags don't require
+    /// any of that, since they go through the `tlv::register` hook instead.
+    fn build(packet_id: u8, tag: tlv::Tag, value: Bytes, checksum: bool) -> Result<Self> {
         let header = Header {
-            packet_id: rng.gen(),
+            packet_id,
             tag,
             length: value.len(),
+            checksum,
         };
 
         match tag {
             tlv::Tag::DownloadPiece => {
-                let download_piece = tlv::download_piece::DownloadPiece::from_bytes(value)?;
+                let (download_piece, remainder) =
+                    tlv::download_piece::DownloadPiece::try_parse(value)?;
+                Self::ensure_consumed(tag, remainder)?;
                 Ok(Vortex::DownloadPiece(header, download_piece))
             }
             tlv::Tag::PieceContent => {
-                let piece_content = tlv::piece_content::PieceContent::from_bytes(value)?;
+                let (piece_content, remainder) =
+                    tlv::piece_content::PieceContent::try_parse(value)?;
+                Self::ensure_consumed(tag, remainder)?;
                 Ok(Vortex::PieceContent(header, piece_content))
             }
-            tlv::Tag::Reserved(_) => Ok(Vortex::Reserved(header)),
             tlv::Tag::Error => {
-                let err = tlv::error::Error::from_bytes(value)?;
+                let (err, remainder) = tlv::error::Error::try_parse(value)?;
+                Self::ensure_consumed(tag, remainder)?;
                 Ok(Vortex::Error(header, err))
             }
+            tlv::Tag::Reserved(n) => {
+                let (value, remainder) = tlv::parse_reserved(n, value)?;
+                Self::ensure_consumed(tag, remainder)?;
+                Ok(Vortex::Reserved(header, value))
+            }
         }
     }
 
-    /// packet_id returns the packet identifier of the Vortex packet.
+    /// ensure_consumed returns `Error::InvalidLength` if a `TryParse` implementation left bytes
+    /// unconsumed, meaning it didn't decode the whole `length`-bounded value.
+    fn ensure_consumed(tag: tlv::Tag, remainder: Bytes) -> Result<()> {
+        if remainder.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidLength(format!(
+                "{} unparsed byte(s) remaining for tag {:?}",
+                remainder.len(),
+                tag
+            )))
+        }
+    }
+
+    /// header returns the header of the Vortex packet.
     #[inline]
-    pub fn packet_id(&self) -> u8 {
+    fn header(&self) -> &Header {
         match self {
-            Vortex::DownloadPiece(header, _) => header.packet_id,
-            Vortex::PieceContent(header, _) => header.packet_id,
-            Vortex::Reserved(header) => header.packet_id,
-            Vortex::Error(header, _) => header.packet_id,
+            Vortex::DownloadPiece(header, _) => header,
+            Vortex::PieceContent(header, _) => header,
+            Vortex::Reserved(header, _) => header,
+            Vortex::Error(header, _) => header,
         }
     }
 
+    /// packet_id returns the packet identifier of the Vortex packet.
+    #[inline]
+    pub fn packet_id(&self) -> u8 {
+        self.header().packet_id
+    }
+
     /// tag returns the tag of the Vortex packet.
     #[inline]
     pub fn tag(&self) -> &tlv::Tag {
-        match self {
-            Vortex::DownloadPiece(header, _) => &header.tag,
-            Vortex::PieceContent(header, _) => &header.tag,
-            Vortex::Reserved(header) => &header.tag,
-            Vortex::Error(header, _) => &header.tag,
-        }
+        &self.header().tag
     }
 
     /// length returns the length of the value field.
     #[inline]
     pub fn length(&self) -> usize {
-        match self {
-            Vortex::DownloadPiece(header, _) => header.length,
-            Vortex::PieceContent(header, _) => header.length,
-            Vortex::Reserved(header) => header.length,
-            Vortex::Error(header, _) => header.length,
-        }
+        self.header().length
     }
 
-    /// from_bytes creates a Vortex packet from a byte slice.
+    /// from_bytes creates a Vortex packet from a byte slice. If the header's checksum flag is
+    /// set, the trailing CRC32C is verified before the value is parsed.
     pub fn from_bytes(bytes: Bytes) -> Result<Self> {
         if bytes.len() < HEADER_SIZE {
             return Err(Error::InvalidPacket(format!(
@@ -135,60 +199,84 @@ impl Vortex {
 
         let mut bytes = BytesMut::from(bytes);
         let header = bytes.split_to(HEADER_SIZE);
-        let value = bytes;
         let packet_id = header[0];
         let tag = header[1]
             .try_into()
             .map_err(|err| Error::InvalidPacket(format!("invalid tag value: {:?}", err)))?;
-        let length = u32::from_be_bytes(header[2..HEADER_SIZE].try_into()?) as usize;
+        let raw_length = u32::from_be_bytes(header[2..HEADER_SIZE].try_into()?);
+        let checksum = raw_length & CHECKSUM_FLAG != 0;
+        let length = (raw_length & !CHECKSUM_FLAG) as usize;
 
-        // Check if the value length matches the specified length.
-        if value.len() != length {
+        // Check if the remaining bytes match the value length, plus a trailing CRC32C if present.
+        let expected_len = length + if checksum { CRC_SIZE } else { 0 };
+        if bytes.len() != expected_len {
             return Err(Error::InvalidLength(format!(
-                "value len {} != declared length {}",
-                value.len(),
-                length
+                "remaining len {} != declared length {expected_len}",
+                bytes.len(),
             )));
         }
 
-        let header = Header {
-            packet_id,
-            tag,
-            length,
-        };
+        let value = bytes.split_to(length).freeze();
 
-        match tag {
-            tlv::Tag::DownloadPiece => {
-                let download_piece =
-                    tlv::download_piece::DownloadPiece::from_bytes(value.freeze())?;
-                Ok(Vortex::DownloadPiece(header, download_piece))
-            }
-            tlv::Tag::PieceContent => {
-                let piece_content = tlv::piece_content::PieceContent::from_bytes(value.freeze())?;
-                Ok(Vortex::PieceContent(header, piece_content))
-            }
-            tlv::Tag::Reserved(_) => Ok(Vortex::Reserved(header)),
-            tlv::Tag::Error => {
-                let error = tlv::error::Error::from_bytes(value.freeze())?;
-                Ok(Vortex::Error(header, error))
+        if checksum {
+            let expected_crc = u32::from_le_bytes(bytes[..CRC_SIZE].try_into()?);
+            let computed_crc = crc32c::crc32c_append(crc32c::crc32c(&header), &value);
+
+            if computed_crc != expected_crc {
+                return Err(Error::ChecksumMismatch(format!(
+                    "computed crc32c {computed_crc:#010x} != header crc32c {expected_crc:#010x}"
+                )));
             }
         }
+
+        Self::build(packet_id, tag, value, checksum)
     }
 
-    /// to_bytes converts the Vortex packet to a byte slice.
-    pub fn to_bytes(&self) -> bytes::Bytes {
-        let (header, value) = match self {
-            Vortex::DownloadPiece(header, download_piece) => (header, download_piece.to_bytes()),
-            Vortex::PieceContent(header, piece_content) => (header, piece_content.to_bytes()),
-            Vortex::Reserved(header) => (header, bytes::Bytes::new()),
-            Vortex::Error(header, err) => (header, err.to_bytes()),
-        };
+    /// serialized_value_len returns the exact encoded length of the value field, without
+    /// materializing it, so callers can size a buffer up front.
+    fn serialized_value_len(&self) -> usize {
+        match self {
+            Vortex::DownloadPiece(_, download_piece) => download_piece.serialized_len(),
+            Vortex::PieceContent(_, piece_content) => piece_content.serialized_len(),
+            Vortex::Reserved(_, value) => value.serialized_len(),
+            Vortex::Error(_, err) => err.serialized_len(),
+        }
+    }
+
+    /// write_into appends the wire representation of this packet to `buf`, reserving exactly the
+    /// space it needs. This backs both `to_bytes` and `VortexCodec`'s `Encoder` impl, so the two
+    /// never drift apart. When the packet was built with a checksum, the length field's
+    /// `CHECKSUM_FLAG` bit is set and a trailing little-endian CRC32C over the header and value is
+    /// appended after it.
+    pub(crate) fn write_into(&self, buf: &mut BytesMut) {
+        let header = self.header();
+        let serialized_len = self.serialized_value_len();
 
-        let mut bytes = BytesMut::with_capacity(HEADER_SIZE + value.len());
-        bytes.put_u8(header.packet_id);
-        bytes.put_u8(header.tag.into());
-        bytes.put_u32(value.len() as u32);
-        bytes.extend_from_slice(&value);
+        buf.reserve(HEADER_SIZE + serialized_len + if header.checksum { CRC_SIZE } else { 0 });
+        let header_start = buf.len();
+
+        buf.put_u8(header.packet_id);
+        buf.put_u8(header.tag.into());
+        buf.put_u32(serialized_len as u32 | if header.checksum { CHECKSUM_FLAG } else { 0 });
+
+        match self {
+            Vortex::DownloadPiece(_, download_piece) => download_piece.serialize_into(buf),
+            Vortex::PieceContent(_, piece_content) => piece_content.serialize_into(buf),
+            Vortex::Reserved(_, value) => value.serialize_into(buf),
+            Vortex::Error(_, err) => err.serialize_into(buf),
+        }
+
+        if header.checksum {
+            let crc = crc32c::crc32c(&buf[header_start..]);
+            buf.put_u32_le(crc);
+        }
+    }
+
+    /// to_bytes converts the Vortex packet to a byte slice, sizing the buffer exactly via
+    /// `serialized_len` instead of materializing the value first to measure it.
+    pub fn to_bytes(&self) -> bytes::Bytes {
+        let mut bytes = BytesMut::new();
+        self.write_into(&mut bytes);
         bytes.freeze()
     }
 }
@@ -213,7 +301,8 @@ mod tests {
     #[test]
     fn test_new_piece_content() {
         let tag = Tag::PieceContent;
-        let value = Bytes::from("Hello, world!");
+        // A leading identity (0) content-encoding byte, followed by the piece bytes.
+        let value = Bytes::from([&[0u8][..], b"Hello, world!"].concat());
         let packet = Vortex::new(tag, value.clone()).expect("Failed to create Vortex packet");
 
         assert_eq!(packet.packet_id(), packet.packet_id());
@@ -256,4 +345,39 @@ mod tests {
         assert_eq!(packet.tag(), &tag);
         assert_eq!(packet.length(), value.len());
     }
+
+    #[test]
+    fn test_checksummed_roundtrip() {
+        let tag = Tag::DownloadPiece;
+        let value = Bytes::from("a".repeat(32) + "-42");
+        let packet =
+            Vortex::new_checksummed(tag, value.clone()).expect("Failed to create Vortex packet");
+        let bytes = packet.to_bytes();
+
+        // A checksummed packet carries 4 more bytes than its checksum-less equivalent.
+        assert_eq!(bytes.len(), HEADER_SIZE + value.len() + CRC_SIZE);
+
+        let parsed_packet =
+            Vortex::from_bytes(bytes).expect("Failed to parse checksummed Vortex packet");
+        assert_eq!(parsed_packet.tag(), packet.tag());
+        assert_eq!(parsed_packet.length(), packet.length());
+    }
+
+    #[test]
+    fn test_checksum_mismatch_is_detected() {
+        let tag = Tag::DownloadPiece;
+        let value = Bytes::from("a".repeat(32) + "-42");
+        let packet =
+            Vortex::new_checksummed(tag, value).expect("Failed to create Vortex packet");
+        let mut bytes = BytesMut::from(packet.to_bytes());
+
+        // Flip every bit of the last value byte, leaving the trailing CRC32C stale.
+        let last = bytes.len() - CRC_SIZE - 1;
+        bytes[last] ^= 0xff;
+
+        assert!(matches!(
+            Vortex::from_bytes(bytes.freeze()),
+            Err(Error::ChecksumMismatch(_))
+        ));
+    }
 }
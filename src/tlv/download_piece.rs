@@ -0,0 +1,179 @@
+/*
+ *     Copyright 2025 The Dragonfly Authors
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::encoding::Encoding;
+use super::{Serialize, TryParse};
+use crate::error::{Error, Result};
+use bytes::{Bytes, BytesMut};
+
+/// DownloadPiece is the value of a `Tag::DownloadPiece` packet, requesting a single piece of a
+/// task's content by task ID and piece number. The wire format is
+/// `{task_id}-{piece_number}[:{accept_encodings}]`, where `accept_encodings` is a comma-separated
+/// list of content-encoding tags the requester accepts a `PieceContent` response compressed with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadPiece {
+    pub task_id: String,
+    pub piece_number: u32,
+    pub accept_encodings: Vec<Encoding>,
+}
+
+/// DownloadPiece implements the DownloadPiece functions.
+impl DownloadPiece {
+    /// new creates a new DownloadPiece.
+    pub fn new(task_id: String, piece_number: u32, accept_encodings: Vec<Encoding>) -> Self {
+        Self {
+            task_id,
+            piece_number,
+            accept_encodings,
+        }
+    }
+
+    /// negotiated_encoding returns the content-encoding a responder should compress its
+    /// `PieceContent` reply with: the requester's first listed preference, or
+    /// `Encoding::Identity` if it listed none.
+    pub fn negotiated_encoding(&self) -> Encoding {
+        self.accept_encodings
+            .first()
+            .copied()
+            .unwrap_or(Encoding::Identity)
+    }
+}
+
+/// TryParse decodes a DownloadPiece from its
+/// `{task_id}-{piece_number}[:{accept_encodings}]` wire representation.
+impl TryParse for DownloadPiece {
+    fn try_parse(bytes: Bytes) -> Result<(Self, Bytes)> {
+        let value = std::str::from_utf8(&bytes)
+            .map_err(|err| Error::InvalidPacket(format!("invalid download piece value: {err}")))?;
+
+        let (base, accept_encodings) = match value.split_once(':') {
+            Some((base, accept_encodings)) => (base, accept_encodings),
+            None => (value, ""),
+        };
+
+        let (task_id, piece_number) = base.rsplit_once('-').ok_or_else(|| {
+            Error::InvalidPacket(format!("missing piece number separator in {base:?}"))
+        })?;
+
+        let piece_number = piece_number.parse().map_err(|err| {
+            Error::InvalidPacket(format!("invalid piece number {piece_number:?}: {err}"))
+        })?;
+
+        let accept_encodings = if accept_encodings.is_empty() {
+            Vec::new()
+        } else {
+            accept_encodings
+                .split(',')
+                .map(|tag| {
+                    let tag: u8 = tag.parse().map_err(|err| {
+                        Error::InvalidPacket(format!("invalid content encoding tag {tag:?}: {err}"))
+                    })?;
+                    Encoding::try_from(tag)
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        Ok((
+            Self {
+                task_id: task_id.to_string(),
+                piece_number,
+                accept_encodings,
+            },
+            Bytes::new(),
+        ))
+    }
+}
+
+/// Serialize encodes a DownloadPiece as `{task_id}-{piece_number}[:{accept_encodings}]`.
+impl Serialize for DownloadPiece {
+    fn serialize_into(&self, buf: &mut BytesMut) {
+        buf.extend_from_slice(self.task_id.as_bytes());
+        buf.extend_from_slice(b"-");
+        buf.extend_from_slice(self.piece_number.to_string().as_bytes());
+
+        if !self.accept_encodings.is_empty() {
+            buf.extend_from_slice(b":");
+            for (i, encoding) in self.accept_encodings.iter().enumerate() {
+                if i > 0 {
+                    buf.extend_from_slice(b",");
+                }
+                buf.extend_from_slice(u8::from(*encoding).to_string().as_bytes());
+            }
+        }
+    }
+
+    fn serialized_len(&self) -> usize {
+        let mut len = self.task_id.len() + 1 + self.piece_number.to_string().len();
+        if !self.accept_encodings.is_empty() {
+            len += 1;
+            len += self
+                .accept_encodings
+                .iter()
+                .map(|encoding| u8::from(*encoding).to_string().len())
+                .sum::<usize>();
+            len += self.accept_encodings.len() - 1;
+        }
+        len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_parse_and_serialize_roundtrip() {
+        let value = Bytes::from("a".repeat(32) + "-42");
+        let (download_piece, remainder) =
+            DownloadPiece::try_parse(value).expect("Failed to parse DownloadPiece");
+
+        assert!(remainder.is_empty());
+        assert_eq!(download_piece.task_id, "a".repeat(32));
+        assert_eq!(download_piece.piece_number, 42);
+        assert!(download_piece.accept_encodings.is_empty());
+        assert_eq!(download_piece.negotiated_encoding(), Encoding::Identity);
+
+        let mut buf = BytesMut::new();
+        download_piece.serialize_into(&mut buf);
+        assert_eq!(buf.len(), download_piece.serialized_len());
+        assert_eq!(buf.freeze(), Bytes::from("a".repeat(32) + "-42"));
+    }
+
+    #[test]
+    fn test_try_parse_missing_separator() {
+        let value = Bytes::from("a".repeat(32) + "42");
+        assert!(DownloadPiece::try_parse(value).is_err());
+    }
+
+    #[test]
+    fn test_accept_encodings_roundtrip() {
+        let download_piece = DownloadPiece::new(
+            "a".repeat(32),
+            42,
+            vec![Encoding::Zstd, Encoding::Gzip],
+        );
+
+        let mut buf = BytesMut::new();
+        download_piece.serialize_into(&mut buf);
+        assert_eq!(buf.len(), download_piece.serialized_len());
+
+        let (parsed, remainder) =
+            DownloadPiece::try_parse(buf.freeze()).expect("Failed to parse DownloadPiece");
+        assert!(remainder.is_empty());
+        assert_eq!(parsed, download_piece);
+        assert_eq!(parsed.negotiated_encoding(), Encoding::Zstd);
+    }
+}
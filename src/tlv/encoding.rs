@@ -0,0 +1,54 @@
+/*
+ *     Copyright 2025 The Dragonfly Authors
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+
+/// Encoding identifies how a `PieceContent` value is compressed on the wire, carried as the
+/// one-byte content-encoding field prepended to the value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Identity means the content is stored uncompressed.
+    Identity,
+
+    /// Zstd means the content is compressed with zstd.
+    Zstd,
+
+    /// Gzip means the content is compressed with gzip.
+    Gzip,
+}
+
+impl TryFrom<u8> for Encoding {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Encoding::Identity),
+            1 => Ok(Encoding::Zstd),
+            2 => Ok(Encoding::Gzip),
+            n => Err(Error::InvalidPacket(format!("unknown content encoding {n}"))),
+        }
+    }
+}
+
+impl From<Encoding> for u8 {
+    fn from(encoding: Encoding) -> Self {
+        match encoding {
+            Encoding::Identity => 0,
+            Encoding::Zstd => 1,
+            Encoding::Gzip => 2,
+        }
+    }
+}
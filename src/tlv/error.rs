@@ -0,0 +1,92 @@
+/*
+ *     Copyright 2025 The Dragonfly Authors
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{Serialize, TryParse};
+use crate::error::{Error as CrateError, Result};
+use bytes::{Bytes, BytesMut};
+
+/// Error is the value of a `Tag::Error` packet, reporting a peer-side failure in place of the
+/// expected response. The wire format is `{code}:{message}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub code: u8,
+    pub message: String,
+}
+
+/// Error implements the Error functions.
+impl Error {
+    /// new creates a new Error value.
+    pub fn new(code: u8, message: String) -> Self {
+        Self { code, message }
+    }
+}
+
+/// TryParse decodes an Error value from its `{code}:{message}` wire representation.
+impl TryParse for Error {
+    fn try_parse(bytes: Bytes) -> Result<(Self, Bytes)> {
+        let value = std::str::from_utf8(&bytes)
+            .map_err(|err| CrateError::InvalidPacket(format!("invalid error value: {err}")))?;
+
+        let (code, message) = value.split_once(':').ok_or_else(|| {
+            CrateError::InvalidPacket(format!("missing code separator in {value:?}"))
+        })?;
+
+        let code = code.parse().map_err(|err| {
+            CrateError::InvalidPacket(format!("invalid error code {code:?}: {err}"))
+        })?;
+
+        Ok((
+            Self {
+                code,
+                message: message.to_string(),
+            },
+            Bytes::new(),
+        ))
+    }
+}
+
+/// Serialize encodes an Error value as `{code}:{message}`.
+impl Serialize for Error {
+    fn serialize_into(&self, buf: &mut BytesMut) {
+        buf.extend_from_slice(self.code.to_string().as_bytes());
+        buf.extend_from_slice(b":");
+        buf.extend_from_slice(self.message.as_bytes());
+    }
+
+    fn serialized_len(&self) -> usize {
+        self.code.to_string().len() + 1 + self.message.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_parse_and_serialize_roundtrip() {
+        let value = Bytes::from("1:Error message");
+        let (err, remainder) = Error::try_parse(value).expect("Failed to parse Error");
+
+        assert!(remainder.is_empty());
+        assert_eq!(err.code, 1);
+        assert_eq!(err.message, "Error message");
+
+        let mut buf = BytesMut::new();
+        err.serialize_into(&mut buf);
+        assert_eq!(buf.len(), err.serialized_len());
+        assert_eq!(buf.freeze(), Bytes::from("1:Error message"));
+    }
+}
@@ -0,0 +1,226 @@
+/*
+ *     Copyright 2025 The Dragonfly Authors
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::error::{Error, Result};
+use bytes::{Bytes, BytesMut};
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{OnceLock, RwLock};
+
+pub mod download_piece;
+pub mod encoding;
+pub mod error;
+pub mod piece_content;
+pub mod raw;
+
+/// TryParse decodes a TLV value from its wire bytes.
+///
+/// Implementors receive exactly the `length`-bounded value bytes of a packet and must return the
+/// parsed value together with whatever was left unconsumed. `Vortex::from_bytes` treats a
+/// non-empty remainder as `Error::InvalidLength`, so a well-behaved implementation consumes the
+/// entire input.
+pub trait TryParse: Sized {
+    /// try_parse decodes `Self` from `bytes`, returning the parsed value and any unconsumed
+    /// remainder.
+    fn try_parse(bytes: Bytes) -> Result<(Self, Bytes)>;
+}
+
+/// Serialize encodes a TLV value onto the wire.
+pub trait Serialize {
+    /// serialize_into appends the wire representation of `self` to `buf`.
+    fn serialize_into(&self, buf: &mut BytesMut);
+
+    /// serialized_len returns the exact number of bytes `serialize_into` will write, so callers
+    /// can size their buffer up front instead of over-allocating.
+    fn serialized_len(&self) -> usize;
+}
+
+/// Value is the trait object bound for TLV value types registered against a `Tag::Reserved` tag.
+/// It lets `Vortex::from_bytes` hand a registered downstream crate back a typed packet instead of
+/// the opaque bytes we'd otherwise have to return for a tag we don't know about.
+pub trait Value: Serialize + Any + Send + Sync {
+    /// as_any supports downcasting a `Value` trait object back to its concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    /// fmt_debug backs the `Debug` impl for `dyn Value`, since `Debug` itself isn't object safe
+    /// to derive across an unknown set of implementors.
+    fn fmt_debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result;
+}
+
+impl<T> Value for T
+where
+    T: Serialize + Debug + Any + Send + Sync,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn fmt_debug(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl Debug for dyn Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_debug(f)
+    }
+}
+
+/// ParseFn decodes a boxed `Value` from the wire bytes of a registered reserved tag.
+type ParseFn = fn(Bytes) -> Result<(Box<dyn Value>, Bytes)>;
+
+/// registry returns the process-wide map of reserved tag numbers to their registered parsers.
+fn registry() -> &'static RwLock<HashMap<u8, ParseFn>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<u8, ParseFn>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// register associates a `Tag::Reserved(tag)` value with a concrete `TryParse`/`Serialize`
+/// implementation, so that `Vortex::from_bytes` returns a typed `Value` for that tag instead of
+/// `raw::RawValue`.
+///
+/// Registering the same tag twice replaces the previous registration.
+pub fn register<T>(tag: u8)
+where
+    T: TryParse + Value + 'static,
+{
+    registry().write().unwrap().insert(tag, |bytes| {
+        let (value, remainder) = T::try_parse(bytes)?;
+        Ok((Box::new(value) as Box<dyn Value>, remainder))
+    });
+}
+
+/// parse_reserved decodes the value of a `Tag::Reserved(tag)` packet, dispatching to a
+/// registered parser if one exists and falling back to `raw::RawValue` otherwise.
+pub(crate) fn parse_reserved(tag: u8, bytes: Bytes) -> Result<(Box<dyn Value>, Bytes)> {
+    let parse = registry().read().unwrap().get(&tag).copied();
+    match parse {
+        Some(parse) => parse(bytes),
+        None => {
+            let (raw, remainder) = raw::RawValue::try_parse(bytes)?;
+            Ok((Box::new(raw) as Box<dyn Value>, remainder))
+        }
+    }
+}
+
+/// Tag specifies the type of the value in a Vortex packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tag {
+    /// DownloadPiece requests a single piece of a task's content.
+    DownloadPiece,
+
+    /// PieceContent carries the bytes of a requested piece, prefixed by a content-encoding byte
+    /// and compressed according to it.
+    PieceContent,
+
+    /// Reserved is a tag value not defined by the core protocol, available for downstream crates
+    /// to register their own TLV value type against via `register`.
+    Reserved(u8),
+
+    /// Error reports a peer-side failure in place of the expected response.
+    Error,
+}
+
+impl TryFrom<u8> for Tag {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Tag::DownloadPiece),
+            1 => Ok(Tag::PieceContent),
+            2 => Ok(Tag::Error),
+            n => Ok(Tag::Reserved(n)),
+        }
+    }
+}
+
+impl From<Tag> for u8 {
+    fn from(tag: Tag) -> Self {
+        match tag {
+            Tag::DownloadPiece => 0,
+            Tag::PieceContent => 1,
+            Tag::Error => 2,
+            Tag::Reserved(n) => n,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Ping(u8);
+
+    impl TryParse for Ping {
+        fn try_parse(bytes: Bytes) -> Result<(Self, Bytes)> {
+            if bytes.len() != 1 {
+                return Err(Error::InvalidLength(format!(
+                    "expected 1 byte for Ping, got {}",
+                    bytes.len()
+                )));
+            }
+            Ok((Self(bytes[0]), Bytes::new()))
+        }
+    }
+
+    impl Serialize for Ping {
+        fn serialize_into(&self, buf: &mut BytesMut) {
+            buf.extend_from_slice(&[self.0]);
+        }
+
+        fn serialized_len(&self) -> usize {
+            1
+        }
+    }
+
+    #[test]
+    fn test_register_dispatches_to_the_registered_parser() {
+        const PING_TAG: u8 = 200;
+        register::<Ping>(PING_TAG);
+
+        let (value, remainder) =
+            parse_reserved(PING_TAG, Bytes::from_static(&[7])).expect("Failed to parse Ping");
+
+        assert!(remainder.is_empty());
+        assert_eq!(value.as_any().downcast_ref::<Ping>(), Some(&Ping(7)));
+    }
+
+    #[test]
+    fn test_parse_reserved_falls_back_to_raw_value_when_unregistered() {
+        let (value, remainder) = parse_reserved(201, Bytes::from_static(&[1, 2, 3]))
+            .expect("Failed to parse unregistered reserved tag");
+
+        assert!(remainder.is_empty());
+        assert_eq!(
+            value.as_any().downcast_ref::<raw::RawValue>(),
+            Some(&raw::RawValue(Bytes::from_static(&[1, 2, 3])))
+        );
+    }
+
+    #[test]
+    fn test_tag_u8_roundtrip() {
+        for tag in [
+            Tag::DownloadPiece,
+            Tag::PieceContent,
+            Tag::Error,
+            Tag::Reserved(200),
+        ] {
+            assert_eq!(Tag::try_from(u8::from(tag)).unwrap(), tag);
+        }
+    }
+}
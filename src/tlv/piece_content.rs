@@ -0,0 +1,228 @@
+/*
+ *     Copyright 2025 The Dragonfly Authors
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::encoding::Encoding;
+use super::{Serialize, TryParse};
+use crate::error::{Error, Result};
+use bytes::{Bytes, BytesMut};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// PieceContent is the value of a `Tag::PieceContent` packet, carrying the bytes of a requested
+/// piece. The wire format is a one-byte content-encoding field followed by the value, compressed
+/// according to that encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PieceContent {
+    encoding: Encoding,
+    // encoded is the wire payload that follows the encoding byte: `content` compressed under
+    // `encoding`. Cached alongside `content` so `serialize_into` never has to recompress.
+    encoded: Bytes,
+    content: Bytes,
+}
+
+/// PieceContent implements the PieceContent functions.
+impl PieceContent {
+    /// new creates a new, uncompressed PieceContent from raw piece bytes.
+    pub fn new(content: Bytes) -> Self {
+        Self {
+            encoding: Encoding::Identity,
+            encoded: content.clone(),
+            content,
+        }
+    }
+
+    /// compressed creates a new PieceContent by compressing `data` under `encoding`.
+    pub fn compressed(data: Bytes, encoding: Encoding) -> Result<Self> {
+        let encoded = compress(encoding, &data)?;
+        Ok(Self {
+            encoding,
+            encoded,
+            content: data,
+        })
+    }
+
+    /// content returns the piece bytes, already decompressed.
+    pub fn content(&self) -> &Bytes {
+        &self.content
+    }
+
+    /// encoding returns the content-encoding this piece is compressed under.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+}
+
+/// TryParse reads the content-encoding byte and transparently inflates the value that follows.
+impl TryParse for PieceContent {
+    fn try_parse(bytes: Bytes) -> Result<(Self, Bytes)> {
+        if bytes.is_empty() {
+            return Err(Error::InvalidPacket(
+                "missing content-encoding byte".to_string(),
+            ));
+        }
+
+        let mut bytes = BytesMut::from(bytes);
+        let encoding = Encoding::try_from(bytes.split_to(1)[0])?;
+        let encoded = bytes.freeze();
+        let content = decompress(encoding, &encoded)?;
+
+        Ok((
+            Self {
+                encoding,
+                encoded,
+                content,
+            },
+            Bytes::new(),
+        ))
+    }
+}
+
+/// Serialize writes the content-encoding byte followed by the already-compressed value.
+impl Serialize for PieceContent {
+    fn serialize_into(&self, buf: &mut BytesMut) {
+        buf.extend_from_slice(&[self.encoding.into()]);
+        buf.extend_from_slice(&self.encoded);
+    }
+
+    fn serialized_len(&self) -> usize {
+        1 + self.encoded.len()
+    }
+}
+
+/// compress encodes `data` under `encoding`.
+fn compress(encoding: Encoding, data: &[u8]) -> Result<Bytes> {
+    match encoding {
+        Encoding::Identity => Ok(Bytes::copy_from_slice(data)),
+        Encoding::Zstd => zstd::stream::encode_all(data, 0)
+            .map(Bytes::from)
+            .map_err(|err| Error::InvalidPacket(format!("zstd compression failed: {err}"))),
+        Encoding::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder
+                .write_all(data)
+                .map_err(|err| Error::InvalidPacket(format!("gzip compression failed: {err}")))?;
+            encoder
+                .finish()
+                .map(Bytes::from)
+                .map_err(|err| Error::InvalidPacket(format!("gzip compression failed: {err}")))
+        }
+    }
+}
+
+/// MAX_DECOMPRESSED_LEN bounds the size `decompress` will inflate a value to, matching the
+/// protocol's 1 GiB value size limit. Without this, a peer could send a tiny compressed payload
+/// that inflates to gigabytes, forcing an unbounded allocation (a decompression bomb).
+const MAX_DECOMPRESSED_LEN: u64 = 1 << 30;
+
+/// decompress decodes `data` as produced by `compress` under `encoding`, refusing to inflate past
+/// `MAX_DECOMPRESSED_LEN`.
+fn decompress(encoding: Encoding, data: &[u8]) -> Result<Bytes> {
+    match encoding {
+        Encoding::Identity => Ok(Bytes::copy_from_slice(data)),
+        Encoding::Zstd => {
+            let decoder = zstd::stream::read::Decoder::new(data).map_err(|err| {
+                Error::InvalidPacket(format!("zstd decompression failed: {err}"))
+            })?;
+            read_bounded("zstd", decoder)
+        }
+        Encoding::Gzip => read_bounded("gzip", GzDecoder::new(data)),
+    }
+}
+
+/// read_bounded inflates `reader` fully into memory, failing with `Error::InvalidPacket` instead
+/// of completing the read if the decompressed output would exceed `MAX_DECOMPRESSED_LEN`.
+fn read_bounded(encoding_name: &str, reader: impl Read) -> Result<Bytes> {
+    let mut out = Vec::new();
+    reader
+        .take(MAX_DECOMPRESSED_LEN + 1)
+        .read_to_end(&mut out)
+        .map_err(|err| Error::InvalidPacket(format!("{encoding_name} decompression failed: {err}")))?;
+
+    if out.len() as u64 > MAX_DECOMPRESSED_LEN {
+        return Err(Error::InvalidPacket(format!(
+            "{encoding_name} decompressed value exceeds {MAX_DECOMPRESSED_LEN} byte limit"
+        )));
+    }
+
+    Ok(Bytes::from(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_parse_and_serialize_roundtrip() {
+        let mut wire = BytesMut::new();
+        wire.extend_from_slice(&[0]);
+        wire.extend_from_slice(b"Hello, world!");
+
+        let (piece_content, remainder) =
+            PieceContent::try_parse(wire.freeze()).expect("Failed to parse PieceContent");
+
+        assert!(remainder.is_empty());
+        assert_eq!(piece_content.encoding(), Encoding::Identity);
+        assert_eq!(piece_content.content(), &Bytes::from("Hello, world!"));
+
+        let mut buf = BytesMut::new();
+        piece_content.serialize_into(&mut buf);
+        assert_eq!(buf.len(), piece_content.serialized_len());
+    }
+
+    #[test]
+    fn test_compressed_roundtrips_through_the_wire() {
+        let data = Bytes::from("a".repeat(256));
+
+        for encoding in [Encoding::Identity, Encoding::Zstd, Encoding::Gzip] {
+            let piece_content = PieceContent::compressed(data.clone(), encoding)
+                .unwrap_or_else(|err| panic!("Failed to compress with {encoding:?}: {err}"));
+
+            let mut buf = BytesMut::new();
+            piece_content.serialize_into(&mut buf);
+            assert_eq!(buf.len(), piece_content.serialized_len());
+
+            let (parsed, remainder) =
+                PieceContent::try_parse(buf.freeze()).expect("Failed to parse PieceContent");
+            assert!(remainder.is_empty());
+            assert_eq!(parsed.encoding(), encoding);
+            assert_eq!(parsed.content(), &data);
+        }
+    }
+
+    #[test]
+    fn test_try_parse_rejects_a_decompression_bomb() {
+        // Stream MAX_DECOMPRESSED_LEN + 1 zero bytes straight into the zstd encoder instead of
+        // materializing them, so the bomb itself stays cheap to construct.
+        let mut encoded = Vec::new();
+        zstd::stream::copy_encode(
+            std::io::repeat(0).take(MAX_DECOMPRESSED_LEN + 1),
+            &mut encoded,
+            0,
+        )
+        .expect("Failed to zstd-encode the oversized source");
+
+        let mut wire = BytesMut::new();
+        wire.extend_from_slice(&[Encoding::Zstd.into()]);
+        wire.extend_from_slice(&encoded);
+
+        assert!(matches!(
+            PieceContent::try_parse(wire.freeze()),
+            Err(Error::InvalidPacket(_))
+        ));
+    }
+}
@@ -0,0 +1,43 @@
+/*
+ *     Copyright 2025 The Dragonfly Authors
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use super::{Serialize, TryParse};
+use crate::error::Result;
+use bytes::{Bytes, BytesMut};
+
+/// RawValue is the fallback value of a `Tag::Reserved` packet whose tag has no parser registered
+/// via `tlv::register`. It preserves the undecoded value bytes so callers can still inspect or
+/// re-serialize the packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawValue(pub Bytes);
+
+/// TryParse treats the entire value as opaque bytes.
+impl TryParse for RawValue {
+    fn try_parse(bytes: Bytes) -> Result<(Self, Bytes)> {
+        Ok((Self(bytes), Bytes::new()))
+    }
+}
+
+/// Serialize writes the opaque bytes unchanged.
+impl Serialize for RawValue {
+    fn serialize_into(&self, buf: &mut BytesMut) {
+        buf.extend_from_slice(&self.0);
+    }
+
+    fn serialized_len(&self) -> usize {
+        self.0.len()
+    }
+}
@@ -0,0 +1,82 @@
+/*
+ *     Copyright 2025 The Dragonfly Authors
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::codec::VortexCodec;
+use crate::error::{Error, Result};
+use crate::tlv::download_piece::DownloadPiece;
+use crate::tlv::piece_content::PieceContent;
+use crate::tlv::{Serialize, Tag};
+use crate::Vortex;
+use bytes::BytesMut;
+use futures::{SinkExt, StreamExt};
+use tokio_util::codec::Framed;
+
+/// Connection is an open QUIC connection to a Vortex peer, used to request pieces of a task's
+/// content from it.
+#[derive(Clone)]
+pub struct Connection {
+    inner: quinn::Connection,
+}
+
+/// Connection implements the Connection functions.
+impl Connection {
+    pub(crate) fn new(inner: quinn::Connection) -> Self {
+        Self { inner }
+    }
+
+    /// download_piece requests a single piece from the peer. Each call opens its own
+    /// bidirectional QUIC stream, so many downloads can run concurrently over the same
+    /// connection without blocking on one another.
+    pub async fn download_piece(&self, request: DownloadPiece) -> Result<PieceContent> {
+        let (send, recv) = self
+            .inner
+            .open_bi()
+            .await
+            .map_err(|err| Error::Transport(err.to_string()))?;
+
+        let mut framed = Framed::new(tokio::io::join(recv, send), VortexCodec::new());
+
+        let mut buf = BytesMut::new();
+        request.serialize_into(&mut buf);
+        let request_packet = Vortex::new(Tag::DownloadPiece, buf.freeze())?;
+        let packet_id = request_packet.packet_id();
+
+        framed.send(request_packet).await?;
+
+        let response = framed.next().await.ok_or_else(|| {
+            Error::Transport("connection closed before a response arrived".to_string())
+        })??;
+
+        if response.packet_id() != packet_id {
+            return Err(Error::InvalidPacket(format!(
+                "response packet_id {} does not match request packet_id {packet_id}",
+                response.packet_id()
+            )));
+        }
+
+        match response {
+            Vortex::PieceContent(_, piece_content) => Ok(piece_content),
+            Vortex::Error(_, err) => Err(Error::Peer {
+                code: err.code,
+                message: err.message,
+            }),
+            other => Err(Error::InvalidPacket(format!(
+                "unexpected response tag {:?}",
+                other.tag()
+            ))),
+        }
+    }
+}